@@ -0,0 +1,165 @@
+use std::time::Instant;
+
+use ggez::graphics;
+use ggez::Context;
+
+use gfx_core::format::Srgba8;
+use gfx_core::handle::RenderTargetView;
+use gfx_core::memory::Typed;
+
+use imgui::*;
+use imgui_gfx_renderer::{Renderer, Shaders};
+
+use crate::{DrawMode, Overlay};
+
+// Latched pointer state, fed into imgui's IO at the top of each frame.
+#[derive(Copy, Clone, Debug, Default)]
+struct MouseState {
+    pos: (f32, f32),
+    pressed: [bool; 3],
+    wheel: f32,
+}
+
+/// Owns the imgui context and its gfx renderer, bridging ggez's gfx backend to
+/// imgui for the parameter-tuning overlay.
+pub struct ImGuiWrapper {
+    imgui: imgui::Context,
+    renderer: Renderer<Srgba8, gfx_device_gl::Resources>,
+    last_frame: Instant,
+    mouse: MouseState,
+}
+
+impl ImGuiWrapper {
+    pub fn new(ctx: &mut Context) -> Self {
+        let mut imgui = imgui::Context::create();
+        imgui.set_ini_filename(None);
+
+        let (factory, device, _encoder, _depth, _color) = graphics::gfx_objects(ctx);
+        let shaders = {
+            let version = device.get_info().shading_language;
+            if version.is_embedded {
+                Shaders::GlSlEs300
+            } else {
+                Shaders::GlSl150
+            }
+        };
+        let renderer = Renderer::init(&mut imgui, &mut *factory, shaders)
+            .expect("failed to initialize imgui renderer");
+
+        Self {
+            imgui,
+            renderer,
+            last_frame: Instant::now(),
+            mouse: MouseState::default(),
+        }
+    }
+
+    /// Build and draw the overlay for this frame, editing `overlay` in place.
+    pub fn render(&mut self, ctx: &mut Context, hidpi_factor: f32, overlay: &mut Overlay) {
+        self.sync_io(ctx, hidpi_factor);
+
+        let ui = self.imgui.frame();
+        build_ui(&ui, overlay);
+
+        let (factory, _device, encoder, _depth, color) = graphics::gfx_objects(ctx);
+        let draw_data = ui.render();
+        self.renderer
+            .render(
+                &mut *factory,
+                encoder,
+                &mut RenderTargetView::new(color),
+                draw_data,
+            )
+            .expect("failed to render imgui overlay");
+    }
+
+    fn sync_io(&mut self, ctx: &mut Context, hidpi_factor: f32) {
+        let now = Instant::now();
+        let delta = now - self.last_frame;
+        self.last_frame = now;
+
+        let (w, h) = graphics::drawable_size(ctx);
+        let io = self.imgui.io_mut();
+        io.delta_time = delta.as_secs_f32();
+        io.display_size = [w, h];
+        io.display_framebuffer_scale = [hidpi_factor, hidpi_factor];
+        io.mouse_pos = [self.mouse.pos.0, self.mouse.pos.1];
+        io.mouse_down = [
+            self.mouse.pressed[0],
+            self.mouse.pressed[1],
+            self.mouse.pressed[2],
+            false,
+            false,
+        ];
+        io.mouse_wheel = self.mouse.wheel;
+        self.mouse.wheel = 0.0;
+    }
+
+    /// Whether imgui wants to consume mouse input this frame (cursor is over a
+    /// window or a widget is active), so callers can suppress world input.
+    pub fn want_capture_mouse(&self) -> bool {
+        self.imgui.io().want_capture_mouse
+    }
+
+    pub fn update_mouse_pos(&mut self, x: f32, y: f32) {
+        self.mouse.pos = (x, y);
+    }
+
+    pub fn update_mouse_down(&mut self, button: ggez::event::MouseButton) {
+        if let Some(slot) = button_slot(button) {
+            self.mouse.pressed[slot] = true;
+        }
+    }
+
+    pub fn update_mouse_up(&mut self, button: ggez::event::MouseButton) {
+        if let Some(slot) = button_slot(button) {
+            self.mouse.pressed[slot] = false;
+        }
+    }
+
+    pub fn update_mouse_wheel(&mut self, y: f32) {
+        self.mouse.wheel += y;
+    }
+}
+
+fn button_slot(button: ggez::event::MouseButton) -> Option<usize> {
+    use ggez::event::MouseButton::*;
+    match button {
+        Left => Some(0),
+        Right => Some(1),
+        Middle => Some(2),
+        _ => None,
+    }
+}
+
+fn build_ui(ui: &Ui, overlay: &mut Overlay) {
+    Window::new(im_str!("Parameters"))
+        .size([300.0, 420.0], Condition::FirstUseEver)
+        .position([10.0, 10.0], Condition::FirstUseEver)
+        .build(ui, || {
+            ui.text(im_str!("Stars: {}", overlay.star_count));
+            ui.text(im_str!("FPS: {:.1}", overlay.fps));
+            ui.separator();
+
+            let config = &mut overlay.config;
+            Slider::new(im_str!("star speed"), 0.0..=50.0).build(ui, &mut config.star_speed);
+            Slider::new(im_str!("star accel"), 0.9..=1.1).build(ui, &mut config.star_accel);
+            Slider::new(im_str!("angle accel"), 0.0..=0.1).build(ui, &mut config.angle_accel);
+            Slider::new(im_str!("time color scale"), -10.0..=10.0)
+                .build(ui, &mut config.star_time_color_scale);
+            Slider::new(im_str!("r scale"), 0.0..=1.0).build(ui, &mut config.r_scale);
+            Slider::new(im_str!("g scale"), 0.0..=1.0).build(ui, &mut config.g_scale);
+            Slider::new(im_str!("b scale"), 0.0..=1.0).build(ui, &mut config.b_scale);
+            Slider::new(im_str!("mouse scale"), 0.0..=20.0).build(ui, &mut config.mouse_scale);
+            Slider::new(im_str!("max segment len"), 1.0..=50.0)
+                .build(ui, &mut config.max_segment_len);
+            ui.separator();
+
+            let mut points = matches!(*overlay.draw_mode, DrawMode::Points);
+            if ui.checkbox(im_str!("points mode"), &mut points) {
+                *overlay.draw_mode = if points { DrawMode::Points } else { DrawMode::Lines };
+            }
+            ui.checkbox(im_str!("primary nearest"), overlay.primary_nearest);
+            ui.checkbox(im_str!("secondary nearest"), overlay.secondary_nearest);
+        });
+}
@@ -0,0 +1,827 @@
+use std::borrow::Cow;
+use std::collections::{HashMap, VecDeque};
+use std::f32::consts::PI;
+use std::fs::File;
+use std::io::BufWriter;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use ggez::{
+        Context, ContextBuilder, GameResult,
+        graphics, nalgebra as na, timer,
+    };
+use ggez::conf;
+use ggez::event;
+
+use gilrs::{Axis, Button, EventType, Gilrs};
+
+mod imgui_wrapper;
+use imgui_wrapper::ImGuiWrapper;
+
+const STAR_DELAY: Duration = Duration::from_millis(100);
+// Side length of a spatial-hash cell, chosen around the typical spacing between
+// neighboring stars so the 3x3 block of cells around a star almost always holds
+// its two nearest neighbors.
+const CELL: f32 = 40.0;
+
+// Analog stick/trigger input below this magnitude is treated as centered.
+const GAMEPAD_DEADZONE: f32 = 0.2;
+// Radius, in world units, that a fully-deflected left stick maps the attractor to.
+const GAMEPAD_ATTRACTOR_RANGE: f32 = 400.0;
+// How strongly the right stick's magnitude drives `angle_delta`.
+const GAMEPAD_ANGLE_DELTA_SCALE: f32 = 0.2;
+// Rates at which the triggers nudge live star speed (per frame) and star accel
+// (per second at the target frame rate: each frame adds the rate times the tick
+// length).
+const TRIGGER_SPEED_RATE: f32 = 0.5;
+const TRIGGER_ACCEL_RATE: f32 = 0.02;
+// Only capture every Nth drawn frame, and shrink each captured frame by this
+// factor, to keep recorded GIFs a reasonable size.
+const RECORD_STRIDE: u32 = 2;
+const RECORD_DOWNSCALE: usize = 2;
+// Fraction the zoom changes per scroll-wheel notch, and the range it clamps to.
+const ZOOM_RATE: f32 = 0.1;
+const MIN_ZOOM: f32 = 0.1;
+const MAX_ZOOM: f32 = 10.0;
+
+/// Every runtime-tunable parameter of the spiral effect, with sensible defaults.
+///
+/// Build one directly via [`SpiralConfig::default`] or fluently with
+/// [`SpiralBuilder`], then hand it to [`run`].
+#[derive(Clone, Debug)]
+pub struct SpiralConfig {
+    pub title: String,
+    pub width: f32,
+    pub height: f32,
+    pub target_fps: u32,
+    pub star_speed: f32,
+    pub star_accel: f32,
+    pub star_time_color_scale: f32,
+    pub angle_accel: f32,
+    pub r_scale: f32,
+    pub g_scale: f32,
+    pub b_scale: f32,
+    pub max_segment_len: f32,
+    pub mouse_scale: f32,
+}
+
+impl Default for SpiralConfig {
+    fn default() -> Self {
+        SpiralConfig {
+            title: "Spiral!".to_owned(),
+            width: 1000.0,
+            height: 1000.0,
+            target_fps: 60,
+            star_speed: 10.0,
+            star_accel: 1.0,
+            star_time_color_scale: -3.0,
+            angle_accel: 0.01,
+            r_scale: 0.2,
+            g_scale: 0.3,
+            b_scale: 0.5,
+            max_segment_len: 5.0,
+            mouse_scale: 5.0,
+        }
+    }
+}
+
+impl SpiralConfig {
+    /// Fraction of a second advanced per simulation tick.
+    fn tick_scale(&self) -> f32 {
+        1.0 / self.target_fps as f32
+    }
+
+    /// Wall-clock duration of a single simulation tick.
+    fn tick_duration(&self) -> Duration {
+        Duration::from_nanos(1_000_000_000 / self.target_fps as u64)
+    }
+}
+
+/// Fluent builder for a [`SpiralConfig`]; start from [`SpiralBuilder::new`] and
+/// finish with [`build`](SpiralBuilder::build).
+pub struct SpiralBuilder {
+    config: SpiralConfig,
+}
+
+impl SpiralBuilder {
+    pub fn new() -> Self {
+        SpiralBuilder { config: SpiralConfig::default() }
+    }
+
+    pub fn with_resolution(mut self, width: f32, height: f32) -> Self {
+        self.config.width = width;
+        self.config.height = height;
+        self
+    }
+
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.config.title = title.into();
+        self
+    }
+
+    pub fn with_target_fps(mut self, fps: u32) -> Self {
+        self.config.target_fps = fps;
+        self
+    }
+
+    pub fn with_star_speed(mut self, speed: f32) -> Self {
+        self.config.star_speed = speed;
+        self
+    }
+
+    pub fn with_star_accel(mut self, accel: f32) -> Self {
+        self.config.star_accel = accel;
+        self
+    }
+
+    pub fn with_color_scales(mut self, r: f32, g: f32, b: f32) -> Self {
+        self.config.r_scale = r;
+        self.config.g_scale = g;
+        self.config.b_scale = b;
+        self
+    }
+
+    pub fn with_time_color_scale(mut self, scale: f32) -> Self {
+        self.config.star_time_color_scale = scale;
+        self
+    }
+
+    pub fn with_angle_accel(mut self, accel: f32) -> Self {
+        self.config.angle_accel = accel;
+        self
+    }
+
+    pub fn with_max_segment_len(mut self, len: f32) -> Self {
+        self.config.max_segment_len = len;
+        self
+    }
+
+    pub fn with_mouse_scale(mut self, scale: f32) -> Self {
+        self.config.mouse_scale = scale;
+        self
+    }
+
+    pub fn build(self) -> SpiralConfig {
+        self.config
+    }
+}
+
+impl Default for SpiralBuilder {
+    fn default() -> Self {
+        SpiralBuilder::new()
+    }
+}
+
+/// Launch the spiral visualizer with the given configuration, blocking until
+/// the window closes.
+pub fn run(config: SpiralConfig) -> GameResult<()> {
+    let (ctx, events) = &mut ContextBuilder::new("spiral", "Abraham Egnor")
+        .window_setup(conf::WindowSetup {
+            title: config.title.clone(),
+            samples: conf::NumSamples::Four,
+            ..Default::default()
+        })
+        .window_mode(conf::WindowMode {
+            width: config.width,
+            height: config.height,
+            ..Default::default()
+        })
+        .build()?;
+    let mut screen = graphics::screen_coordinates(ctx);
+    screen.translate(na::Vector2::new(-screen.w / 2.0, -screen.h / 2.0));
+    graphics::set_screen_coordinates(ctx, screen)?;
+
+    let mut my_game = MyGame::new(ctx, config)?;
+    event::run(ctx, events, &mut my_game)
+}
+
+fn cell_of(pos: na::Point2<f32>) -> (i32, i32) {
+    ((pos.x / CELL).floor() as i32, (pos.y / CELL).floor() as i32)
+}
+
+// Build a small white filled-circle image used as the per-star sprite; tinting
+// and positioning happen per-instance through the sprite batch's `DrawParam`s.
+fn star_image(ctx: &mut Context) -> GameResult<graphics::Image> {
+    const D: usize = 8;
+    let radius = D as f32 / 2.0;
+    let mut rgba = vec![0u8; D * D * 4];
+    for y in 0..D {
+        for x in 0..D {
+            let dx = x as f32 + 0.5 - radius;
+            let dy = y as f32 + 0.5 - radius;
+            if (dx * dx + dy * dy).sqrt() <= radius {
+                let o = (y * D + x) * 4;
+                rgba[o..o + 4].copy_from_slice(&[255, 255, 255, 255]);
+            }
+        }
+    }
+    graphics::Image::from_rgba8(ctx, D as u16, D as u16, &rgba)
+}
+
+// A fixed 6x6x6 RGB color cube (216 entries) used as the GIF's global palette,
+// computed once so every captured frame quantizes against the same colors.
+fn cube_palette() -> Vec<u8> {
+    let mut palette = Vec::with_capacity(216 * 3);
+    for r in 0..6u16 {
+        for g in 0..6u16 {
+            for b in 0..6u16 {
+                palette.push((r * 51) as u8);
+                palette.push((g * 51) as u8);
+                palette.push((b * 51) as u8);
+            }
+        }
+    }
+    palette
+}
+
+fn palette_index(r: u8, g: u8, b: u8) -> u8 {
+    let q = |c: u8| (c as u16 * 5 / 255) as u8;
+    q(r) * 36 + q(g) * 6 + q(b)
+}
+
+// Wrap a gif encoding error as a ggez render error so the `?` operator threads
+// it through the `GameResult` call chain like every other failure here.
+fn gif_err(e: gif::EncodingError) -> ggez::GameError {
+    ggez::GameError::RenderError(format!("gif: {}", e))
+}
+
+// An in-progress GIF recording: an open encoder plus the capture cadence state.
+struct Recorder {
+    encoder: gif::Encoder<BufWriter<File>>,
+    width: u16,
+    height: u16,
+    fps: u32,
+    frame_counter: u32,
+}
+
+impl Recorder {
+    fn new(ctx: &mut Context, fps: u32) -> GameResult<Self> {
+        let image = graphics::screen_image(ctx)?;
+        let width = (image.width() as usize / RECORD_DOWNSCALE) as u16;
+        let height = (image.height() as usize / RECORD_DOWNSCALE) as u16;
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let file = File::create(format!("spiral-{}.gif", stamp))?;
+        let mut encoder =
+            gif::Encoder::new(BufWriter::new(file), width, height, &cube_palette()).map_err(gif_err)?;
+        encoder.set_repeat(gif::Repeat::Infinite).map_err(gif_err)?;
+        Ok(Recorder { encoder, width, height, fps, frame_counter: 0 })
+    }
+
+    // Downscale a full-resolution RGBA readback into the palette and append it.
+    fn push(&mut self, full_w: usize, full_h: usize, rgba: &[u8]) -> GameResult<()> {
+        let (sw, sh) = (self.width as usize, self.height as usize);
+        let mut buffer = vec![0u8; sw * sh];
+        for sy in 0..sh {
+            for sx in 0..sw {
+                let (srcx, srcy) = (sx * RECORD_DOWNSCALE, sy * RECORD_DOWNSCALE);
+                if srcx >= full_w || srcy >= full_h { continue; }
+                let o = (srcy * full_w + srcx) * 4;
+                buffer[sy * sw + sx] = palette_index(rgba[o], rgba[o + 1], rgba[o + 2]);
+            }
+        }
+        let frame = gif::Frame {
+            width: self.width,
+            height: self.height,
+            delay: (100 * RECORD_STRIDE / self.fps.max(1)) as u16,
+            buffer: Cow::Owned(buffer),
+            ..Default::default()
+        };
+        self.encoder.write_frame(&frame).map_err(gif_err)
+    }
+}
+
+struct MyGame {
+    // Configuration.
+    config: SpiralConfig,
+
+    // Graphics.
+    star_batch: graphics::spritebatch::SpriteBatch,
+
+    // World.
+    angle: f32,
+    angle_delta: f32,
+    stars: VecDeque<Star>,
+    grid: HashMap<(i32, i32), Vec<usize>>,
+    last_star: Instant,
+    now: Instant,
+    start: Instant,
+
+    // Input.
+    running: bool,
+    draw_mode: DrawMode,
+    primary_nearest: bool,
+    secondary_nearest: bool,
+    mouse: Option<na::Point2<f32>>,
+    attractor: Option<na::Point2<f32>>,
+    gilrs: Gilrs,
+    recorder: Option<Recorder>,
+
+    // Camera: world-space center of the view and its zoom factor.
+    pan: na::Vector2<f32>,
+    zoom: f32,
+    panning: bool,
+
+    // Live-tuning overlay.
+    imgui_wrapper: ImGuiWrapper,
+    show_overlay: bool,
+    hidpi_factor: f32,
+}
+
+/// Mutable view of the tunable simulation state handed to the imgui overlay so
+/// its widgets edit the live `MyGame`/`SpiralConfig` directly.
+pub(crate) struct Overlay<'a> {
+    pub config: &'a mut SpiralConfig,
+    pub draw_mode: &'a mut DrawMode,
+    pub primary_nearest: &'a mut bool,
+    pub secondary_nearest: &'a mut bool,
+    pub star_count: usize,
+    pub fps: f64,
+}
+
+impl MyGame {
+    fn new(ctx: &mut Context, config: SpiralConfig) -> GameResult<Self> {
+        let now = Instant::now();
+        Ok(MyGame {
+            config,
+            star_batch: graphics::spritebatch::SpriteBatch::new(star_image(ctx)?),
+            angle: 0.0,
+            angle_delta: 0.0,
+            stars: VecDeque::new(),
+            grid: HashMap::new(),
+            last_star: now,
+            now: now,
+            start: now,
+            running: true,
+            draw_mode: DrawMode::Lines,
+            primary_nearest: true,
+            secondary_nearest: false,
+            mouse: None,
+            attractor: None,
+            gilrs: Gilrs::new().expect("aieee, could not initialize gilrs!"),
+            recorder: None,
+            pan: na::Vector2::new(0.0, 0.0),
+            zoom: 1.0,
+            panning: false,
+            imgui_wrapper: ImGuiWrapper::new(ctx),
+            show_overlay: false,
+            hidpi_factor: graphics::window(ctx).get_hidpi_factor() as f32,
+        })
+    }
+
+    // Start a fresh recording, or stop and finalize the current one. Dropping
+    // the `Recorder` flushes its `BufWriter` and lets the encoder write the GIF
+    // trailer, so a toggle-off cleanly closes the file.
+    fn toggle_recording(&mut self, ctx: &mut Context) -> GameResult<()> {
+        if self.recorder.take().is_some() {
+            return Ok(());
+        }
+        self.recorder = Some(Recorder::new(ctx, self.config.target_fps)?);
+        Ok(())
+    }
+
+    // If recording, read back the framebuffer and hand it to the recorder,
+    // honoring the capture stride.
+    fn capture_frame(&mut self, ctx: &mut Context) -> GameResult<()> {
+        let rec = match &mut self.recorder {
+            Some(rec) => rec,
+            None => return Ok(()),
+        };
+        rec.frame_counter += 1;
+        if rec.frame_counter % RECORD_STRIDE != 0 {
+            return Ok(());
+        }
+        let image = graphics::screen_image(ctx)?;
+        let (w, h) = (image.width() as usize, image.height() as usize);
+        let rgba = image.to_rgba8(ctx)?;
+        rec.push(w, h, &rgba)
+    }
+
+    fn toggle_draw_mode(&mut self) {
+        self.draw_mode = match self.draw_mode {
+            DrawMode::Points => DrawMode::Lines,
+            DrawMode::Lines => DrawMode::Points,
+        };
+    }
+
+    // Drain pending gamepad events (keeping gilrs' internal state current) and
+    // fold the first connected pad's current input into the world state.
+    fn poll_gamepad(&mut self) {
+        while let Some(ev) = self.gilrs.next_event() {
+            self.gilrs.update(&ev);
+            if let EventType::ButtonPressed(button, _) = ev.event {
+                match button {
+                    Button::South => self.running = !self.running,
+                    Button::East => self.toggle_draw_mode(),
+                    Button::North => self.primary_nearest = !self.primary_nearest,
+                    Button::West => self.secondary_nearest = !self.secondary_nearest,
+                    _ => (),
+                }
+            }
+        }
+
+        let pad = match self.gilrs.gamepads().next() {
+            Some((_, pad)) => pad,
+            None => return,
+        };
+
+        // Right stick aims the spawn angle; its magnitude drives the spin rate.
+        let (rx, ry) = (pad.value(Axis::RightStickX), pad.value(Axis::RightStickY));
+        let rmag = (rx * rx + ry * ry).sqrt();
+        if rmag > GAMEPAD_DEADZONE {
+            self.angle = ry.atan2(rx);
+            self.angle_delta = rmag * GAMEPAD_ANGLE_DELTA_SCALE;
+        }
+
+        // Left stick steers a virtual attractor when the mouse isn't held.
+        let (lx, ly) = (pad.value(Axis::LeftStickX), pad.value(Axis::LeftStickY));
+        if self.mouse.is_none() && (lx * lx + ly * ly).sqrt() > GAMEPAD_DEADZONE {
+            self.attractor = Some(na::Point2::new(
+                lx * GAMEPAD_ATTRACTOR_RANGE,
+                ly * GAMEPAD_ATTRACTOR_RANGE,
+            ));
+        } else {
+            self.attractor = None;
+        }
+
+        // Triggers tune speed and accel live.
+        let deadzone = |v: f32| if v.abs() > GAMEPAD_DEADZONE { v } else { 0.0 };
+        let (lt, rt) = (deadzone(pad.value(Axis::LeftZ)), deadzone(pad.value(Axis::RightZ)));
+        self.config.star_speed = (self.config.star_speed + (rt - lt) * TRIGGER_SPEED_RATE).max(0.0);
+        self.config.star_accel += (rt - lt) * TRIGGER_ACCEL_RATE * self.config.tick_scale();
+    }
+
+    fn now_f32(&self) -> f32 {
+        timer::duration_to_f64(self.now.duration_since(self.start)) as f32
+    }
+
+    // The currently-visible region of world space, derived from the camera: the
+    // base window size scaled by the inverse zoom and recentered on the pan.
+    fn screen_rect(&self) -> graphics::Rect {
+        let w = self.config.width / self.zoom;
+        let h = self.config.height / self.zoom;
+        graphics::Rect::new(self.pan.x - w / 2.0, self.pan.y - h / 2.0, w, h)
+    }
+
+    // Whether the overlay is up and imgui is claiming the mouse this frame, in
+    // which case world interaction (attractor, pan, zoom) should be suppressed.
+    fn overlay_captures_mouse(&self) -> bool {
+        self.show_overlay && self.imgui_wrapper.want_capture_mouse()
+    }
+
+    // Map a window-pixel coordinate into world space through the camera, the
+    // inverse of `screen_rect`, so mouse input lands where the cursor points.
+    fn screen_to_world(&self, x: f32, y: f32) -> na::Point2<f32> {
+        let rect = self.screen_rect();
+        na::Point2::new(rect.x + x / self.zoom, rect.y + y / self.zoom)
+    }
+
+    fn tick(&mut self, screen: &graphics::Rect) {
+        self.now += self.config.tick_duration();
+        if self.now.duration_since(self.last_star) >= STAR_DELAY {
+            self.last_star = self.now;
+            self.stars.push_back(Star::spawn(&self.config, self.angle, self.now_f32()));
+        }
+        while self.stars.front().map_or(false, |s| !screen.contains(s.pos)) {
+            self.stars.pop_front();
+        }
+        for star in &mut self.stars {
+            star.tick(&self.config);
+        }
+        self.angle += self.angle_delta;
+        if self.angle > 2.0*PI {
+            self.angle -= 2.0*PI;
+        }
+        self.angle_delta += self.config.angle_accel * self.config.tick_scale();
+        if self.angle_delta > 2.0*PI {
+            self.angle_delta -= 2.0*PI;
+        }
+
+        for source in [self.mouse, self.attractor].iter().copied().flatten() {
+            for star in &mut self.stars {
+                let delta = self.config.mouse_scale / (source - star.pos).norm();
+                star.seed += delta;
+            }
+        }
+
+        // Rebuild the spatial-hash grid for this frame's positions so the nearest
+        // search only has to scan the cells around each star.
+        self.grid.clear();
+        for (ix, star) in self.stars.iter().enumerate() {
+            self.grid.entry(cell_of(star.pos)).or_default().push(ix);
+        }
+    }
+
+    // Two nearest neighbors of `ix`, found by scanning the 3x3 block of cells
+    // around its own cell and widening by one ring only if that block held fewer
+    // than two candidates.
+    fn nearest_two(&self, ix: usize) -> (Option<usize>, Option<usize>) {
+        let mut radius = 1;
+        let (mut best, mut second) = self.scan_cells(ix, radius);
+        while second.is_none() && radius < 2 {
+            radius += 1;
+            let (b, s) = self.scan_cells(ix, radius);
+            best = b;
+            second = s;
+        }
+        (best.map(|(_, i)| i), second.map(|(_, i)| i))
+    }
+
+    // Scan the (2*radius+1)^2 block of cells centered on `ix`'s cell and return
+    // the two smallest `distance_sqr_to` results as (nearest, second).
+    fn scan_cells(&self, ix: usize, radius: i32) -> (Option<(f32, usize)>, Option<(f32, usize)>) {
+        let star = &self.stars[ix];
+        let (cx, cy) = cell_of(star.pos);
+        let mut best: Option<(f32, usize)> = None;
+        let mut second: Option<(f32, usize)> = None;
+        for dx in -radius..=radius {
+            for dy in -radius..=radius {
+                let bucket = match self.grid.get(&(cx + dx, cy + dy)) {
+                    Some(b) => b,
+                    None => continue,
+                };
+                for &other_ix in bucket {
+                    if other_ix == ix { continue; }
+                    let d = star.distance_sqr_to(&self.stars[other_ix]);
+                    match best {
+                        Some((bd, _)) if d >= bd => match second {
+                            Some((sd, _)) if d >= sd => (),
+                            _ => second = Some((d, other_ix)),
+                        },
+                        _ => {
+                            second = best;
+                            best = Some((d, other_ix));
+                        }
+                    }
+                }
+            }
+        }
+        (best, second)
+    }
+
+    fn draw_field(&mut self, ctx: &mut Context) -> GameResult<()> {
+        match self.draw_mode {
+            DrawMode::Points => self.draw_points(ctx),
+            DrawMode::Lines => self.draw_lines(ctx),
+        }
+    }
+
+    // Small red dot marking that recording is active. Drawn after the frame is
+    // captured so it never ends up baked into the exported GIF.
+    fn draw_recording_indicator(&self, ctx: &mut Context) -> GameResult<()> {
+        if self.recorder.is_none() {
+            return Ok(());
+        }
+        let screen = graphics::screen_coordinates(ctx);
+        let dot = graphics::Mesh::new_circle(
+            ctx,
+            graphics::DrawMode::fill(),
+            na::Point2::new(screen.x + 20.0, screen.y + 20.0),
+            8.0,
+            0.1,
+            graphics::Color::new(1.0, 0.0, 0.0, 1.0),
+        )?;
+        graphics::draw(ctx, &dot, graphics::DrawParam::default())
+    }
+
+    // Accumulate every star into the sprite batch and issue a single draw, so
+    // the cost is one batched call rather than one `draw` per star.
+    fn draw_points(&mut self, ctx: &mut Context) -> GameResult<()> {
+        let now = self.now_f32();
+        self.star_batch.clear();
+        for star in &self.stars {
+            self.star_batch.add(
+                graphics::DrawParam::new()
+                    .dest(star.pos)
+                    .offset(na::Point2::new(0.5, 0.5))
+                    .color(star.color(&self.config, now)),
+            );
+        }
+        graphics::draw(ctx, &self.star_batch, graphics::DrawParam::default())
+    }
+
+    // Accumulate every line segment into one mesh and issue a single draw. The
+    // per-segment color gradient becomes per-vertex colors in the built mesh.
+    fn draw_lines(&self, ctx: &mut Context) -> GameResult<()> {
+        let now = self.now_f32();
+        let mut builder = graphics::MeshBuilder::new();
+        let mut any = false;
+        for ix in 0..self.stars.len() {
+            let star = &self.stars[ix];
+            let (primary, secondary) = self.nearest_two(ix);
+            if self.secondary_nearest {
+                if let Some(sec) = secondary {
+                    any |= push_line(&mut builder, star.pos, self.stars[sec].pos,
+                        graphics::Color { r: 0.3, g: 0.3, b: 0.3, a: 1.0 })?;
+                }
+            }
+            if self.primary_nearest {
+                if let Some(nearest) = primary {
+                    // Each star draws to its nearest neighbor, but a mutually-nearest
+                    // pair would otherwise be drawn twice; break the tie by index.
+                    let mutual = self.nearest_two(nearest).0 == Some(ix);
+                    if ix < nearest || !mutual {
+                        any |= push_interp_line(&mut builder, &self.config, star,
+                            &self.stars[nearest], now)?;
+                    }
+                }
+            }
+        }
+        if any {
+            let mesh = builder.build(ctx)?;
+            graphics::draw(ctx, &mesh, graphics::DrawParam::default())?;
+        }
+        Ok(())
+    }
+}
+
+// Push one rounded line segment into the mesh builder, skipping degenerate
+// (zero-length) segments the tessellator would reject. Returns whether anything
+// was added.
+fn push_line(builder: &mut graphics::MeshBuilder, start: na::Point2<f32>, end: na::Point2<f32>, color: graphics::Color) -> GameResult<bool> {
+    if (end - start).norm() < f32::EPSILON {
+        return Ok(false);
+    }
+    builder.line(&[start, end], 4.0, color)?;
+    Ok(true)
+}
+
+// Push the color-interpolated run of segments between two stars into the mesh
+// builder. Returns whether any segment was added.
+fn push_interp_line(builder: &mut graphics::MeshBuilder, config: &SpiralConfig, star: &Star, nearest: &Star, now_f32: f32) -> GameResult<bool> {
+    let mut pos = star.pos;
+    let pos_vec = nearest.pos - star.pos;
+    let segments_f32 = (pos_vec.norm() / config.max_segment_len).ceil();
+    if segments_f32 < 1.0 {
+        return Ok(false);
+    }
+    let segments = segments_f32 as i32;
+    let pos_delta = pos_vec / segments_f32;
+    let mut color = star.color(config, now_f32);
+    let nearest_color = nearest.color(config, now_f32);
+    let color_delta = graphics::Color {
+        r: (nearest_color.r - color.r) / segments_f32,
+        g: (nearest_color.g - color.g) / segments_f32,
+        b: (nearest_color.b - color.b) / segments_f32,
+        a: 1.0,
+    };
+    let mut added = false;
+    for _ in 0..segments {
+        let next = pos + pos_delta;
+        added |= push_line(builder, pos, next, color)?;
+        pos = next;
+        color = graphics::Color {
+            r: color.r + color_delta.r,
+            g: color.g + color_delta.g,
+            b: color.b + color_delta.b,
+            a: 1.0,
+        };
+    }
+    Ok(added)
+}
+
+impl event::EventHandler for MyGame {
+    fn update(&mut self, ctx: &mut Context) -> GameResult<()> {
+        self.poll_gamepad();
+        let screen = self.screen_rect();
+        while timer::check_update_time(ctx, self.config.target_fps) {
+            if self.running {
+                self.tick(&screen);
+            } else { timer::yield_now() }
+        }
+        Ok(())
+    }
+
+    fn draw(&mut self, ctx: &mut Context) -> GameResult<()> {
+        graphics::set_screen_coordinates(ctx, self.screen_rect())?;
+        graphics::clear(ctx, graphics::BLACK);
+        self.draw_field(ctx)?;
+        // Capture before the indicator and overlay so recorded frames stay clean.
+        self.capture_frame(ctx)?;
+        self.draw_recording_indicator(ctx)?;
+        if self.show_overlay {
+            let fps = timer::fps(ctx);
+            let MyGame {
+                imgui_wrapper,
+                config,
+                draw_mode,
+                primary_nearest,
+                secondary_nearest,
+                stars,
+                hidpi_factor,
+                ..
+            } = self;
+            let mut overlay = Overlay {
+                config,
+                draw_mode,
+                primary_nearest,
+                secondary_nearest,
+                star_count: stars.len(),
+                fps,
+            };
+            imgui_wrapper.render(ctx, *hidpi_factor, &mut overlay);
+        }
+        graphics::present(ctx)?;
+        Ok(())
+    }
+
+    fn key_up_event(&mut self, ctx: &mut Context, keycode: event::KeyCode, _keymods: event::KeyMods) {
+        use event::KeyCode::*;
+        match keycode {
+            Space => self.running = !self.running,
+            P => self.toggle_draw_mode(),
+            N => self.primary_nearest = !self.primary_nearest,
+            S => self.secondary_nearest = !self.secondary_nearest,
+            R => {
+                if let Err(e) = self.toggle_recording(ctx) {
+                    eprintln!("recording error: {}", e);
+                }
+            }
+            I => self.show_overlay = !self.show_overlay,
+            _ => (),
+        }
+    }
+
+    fn mouse_button_down_event(&mut self, _ctx: &mut Context, button: event::MouseButton, x: f32, y: f32) {
+        self.imgui_wrapper.update_mouse_down(button);
+        if self.overlay_captures_mouse() {
+            return;
+        }
+        match button {
+            event::MouseButton::Left => self.mouse = Some(self.screen_to_world(x, y)),
+            event::MouseButton::Middle | event::MouseButton::Right => self.panning = true,
+            _ => (),
+        }
+    }
+
+    fn mouse_button_up_event(&mut self, _ctx: &mut Context, button: event::MouseButton, _x: f32, _y: f32) {
+        self.imgui_wrapper.update_mouse_up(button);
+        match button {
+            event::MouseButton::Left => self.mouse = None,
+            event::MouseButton::Middle | event::MouseButton::Right => self.panning = false,
+            _ => (),
+        }
+    }
+
+    fn mouse_motion_event(&mut self, _ctx: &mut Context, x: f32, y: f32, dx: f32, dy: f32) {
+        self.imgui_wrapper.update_mouse_pos(x, y);
+        if self.panning {
+            // Drag the world under the cursor: move the view opposite the motion,
+            // scaled into world units by the zoom.
+            self.pan -= na::Vector2::new(dx, dy) / self.zoom;
+        }
+        if self.mouse.is_some() {
+            self.mouse = Some(self.screen_to_world(x, y));
+        }
+    }
+
+    fn mouse_wheel_event(&mut self, ctx: &mut Context, _x: f32, y: f32) {
+        self.imgui_wrapper.update_mouse_wheel(y);
+        if self.overlay_captures_mouse() {
+            return;
+        }
+        // Zoom about the cursor: keep the world point under the cursor fixed by
+        // nudging the pan to compensate for the zoom change.
+        let cursor = ggez::input::mouse::position(ctx);
+        let before = self.screen_to_world(cursor.x, cursor.y);
+        self.zoom = (self.zoom * (1.0 + y * ZOOM_RATE)).max(MIN_ZOOM).min(MAX_ZOOM);
+        let after = self.screen_to_world(cursor.x, cursor.y);
+        self.pan += before - after;
+    }
+}
+
+struct Star {
+    pos: na::Point2<f32>,
+    delta: na::Vector2<f32>,
+    seed: f32,
+}
+
+impl Star {
+    fn spawn(config: &SpiralConfig, angle: f32, now: f32) -> Self {
+        Star {
+            pos: na::Point2::new(0.0, 0.0),
+            delta: na::Vector2::new(angle.cos(), angle.sin()) * config.star_speed,
+            seed: now,
+        }
+    }
+
+    fn color(&self, config: &SpiralConfig, now: f32) -> graphics::Color {
+        let scaled_now = now * config.star_time_color_scale;
+        let r = 0.5 + (0.5 * ((self.seed + scaled_now) * config.r_scale).sin());
+        let g = 0.5 + (0.5 * ((self.seed + scaled_now) * config.g_scale).sin());
+        let b = 0.5 + (0.5 * ((self.seed + scaled_now) * config.b_scale).sin());
+        graphics::Color::new(r, g, b, 1.0)
+    }
+
+    fn distance_sqr_to(&self, other: &Star) -> f32 {
+        (other.pos.x - self.pos.x).powi(2) + (other.pos.y - self.pos.y).powi(2)
+    }
+
+    fn tick(&mut self, config: &SpiralConfig) {
+        self.pos += self.delta * config.tick_scale();
+        self.delta *= config.star_accel;
+    }
+}
+
+enum DrawMode { Points, Lines }
\ No newline at end of file